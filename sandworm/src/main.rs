@@ -1,13 +1,17 @@
 use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
 use std::time::Instant;
 
-use clap::Parser;
-use ignore::WalkBuilder;
-use rayon::prelude::*;
-use regex::Regex;
+use clap::{Parser, ValueEnum};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::{WalkBuilder, WalkState};
+use memmap2::Mmap;
+use regex::bytes::Regex;
+use serde::Serialize;
 
 #[derive(Parser)]
 #[command(name = "sandworm")]
@@ -28,20 +32,205 @@ struct Cli {
     /// Maximum file size in bytes to scan (skip larger files)
     #[arg(long, default_value_t = 10_000_000)]
     max_size: u64,
+
+    /// Output format for the report
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    /// Glob patterns to include; if any are given, only matching paths are scanned
+    #[arg(long = "include", value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Glob patterns to exclude (overrides the built-in junk-dir defaults)
+    #[arg(long = "exclude", value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Honour .gitignore / global gitignore / .git/info/exclude while walking
+    #[arg(long)]
+    respect_gitignore: bool,
+
+    /// Skip encoding detection and only scan valid UTF-8 / ASCII files
+    #[arg(long)]
+    ascii_only: bool,
+
+    /// Obfuscation signatures to look for (repeatable; defaults to all)
+    #[arg(long = "signatures", value_enum, default_values_t = Signature::all())]
+    signatures: Vec<Signature>,
+
+    /// Buffer all findings and group them by file (deterministic order) instead
+    /// of streaming them as they are discovered
+    #[arg(long)]
+    sort: bool,
+}
+
+/// A family of "hidden code" signatures. Each flags a distinct way source can
+/// be visually disguised; `--signatures` selects which ones run.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum Signature {
+    /// Long runs of ASCII spaces/tabs — the classic Shai-Hulud padding.
+    #[serde(rename = "whitespace-obfuscation")]
+    Whitespace,
+    /// Bidirectional control characters used in Trojan-Source attacks.
+    BidiControl,
+    /// Zero-width / invisible characters embedded mid-token.
+    ZeroWidth,
+    /// Long runs of non-ASCII Unicode whitespace.
+    UnicodeWhitespace,
+}
+
+impl Signature {
+    fn all() -> Vec<Signature> {
+        vec![
+            Signature::Whitespace,
+            Signature::BidiControl,
+            Signature::ZeroWidth,
+            Signature::UnicodeWhitespace,
+        ]
+    }
+
+    /// Stable slug used as the SARIF `ruleId` and JSON category.
+    fn slug(self) -> &'static str {
+        match self {
+            Signature::Whitespace => "whitespace-obfuscation",
+            Signature::BidiControl => "bidi-control",
+            Signature::ZeroWidth => "zero-width",
+            Signature::UnicodeWhitespace => "unicode-whitespace",
+        }
+    }
+}
+
+/// Directory names the walker skips by default. Users can override the whole
+/// set by passing their own `--exclude` globs.
+const DEFAULT_EXCLUDES: &[&str] = &[
+    "node_modules",
+    ".git",
+    "vendor",
+    ".pnpm",
+    "dist",
+    "build",
+    ".cache",
+    "__pycache__",
+    ".venv",
+    "venv",
+    ".tox",
+];
+
+/// Compile a list of glob patterns into a `GlobSet`. An empty list yields an
+/// empty set, which matches nothing.
+fn build_globset(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pat in patterns {
+        let glob = Glob::new(pat).unwrap_or_else(|e| panic!("invalid glob {pat:?}: {e}"));
+        builder.add(glob);
+    }
+    builder.build().expect("build glob set")
+}
+
+/// Report serialization mode. `Text` is the human-formatted report; `Json`
+/// and `Sarif` are machine-readable for CI pipelines and code-scanning tools.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    Sarif,
 }
 
 fn default_home() -> String {
     std::env::var("HOME").unwrap_or_else(|_| ".".into())
 }
 
+#[derive(Serialize)]
 struct Finding {
     path: PathBuf,
     line_num: usize,
+    category: Signature,
+    /// Size of the match: whitespace-run length for the whitespace signatures,
+    /// or the count of flagged code points for the Unicode ones.
     ws_count: usize,
+    /// The offending code points (e.g. `U+202E`), for `--verbose` to explain
+    /// *why* a line was flagged. Empty for plain ASCII whitespace runs.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    code_points: Vec<String>,
     line_preview: String,
 }
 
-fn scan_file(path: &PathBuf, pattern: &Regex, max_size: u64) -> Vec<Finding> {
+/// Format a code point in the conventional `U+XXXX` notation.
+fn code_point(c: char) -> String {
+    format!("U+{:04X}", c as u32)
+}
+
+/// Bidirectional control characters abused by Trojan-Source attacks.
+fn is_bidi_control(c: char) -> bool {
+    matches!(c,
+        '\u{202A}'..='\u{202E}'
+        | '\u{2066}'..='\u{2069}'
+        | '\u{061C}'
+        | '\u{200E}'
+        | '\u{200F}'
+    )
+}
+
+/// Zero-width / invisible characters that can hide inside a token.
+fn is_zero_width(c: char) -> bool {
+    matches!(c, '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{FEFF}')
+}
+
+/// Non-ASCII Unicode whitespace (beyond plain space/tab).
+fn is_unicode_whitespace(c: char) -> bool {
+    matches!(c, '\u{00A0}' | '\u{2000}'..='\u{200A}' | '\u{3000}')
+}
+
+/// Decode raw file bytes to UTF-8. A BOM (UTF-16 LE/BE, UTF-8) selects the
+/// encoding directly; otherwise we keep the fast path for valid UTF-8 and fall
+/// back to a lightweight charset guess via `chardetng`, transcoding with
+/// `encoding_rs`. Returns `None` when
+/// `ascii_only` is set and the bytes are not valid UTF-8, preserving the old
+/// "skip on first non-UTF-8 byte" behavior.
+fn decode_bytes(bytes: &[u8], ascii_only: bool) -> Option<String> {
+    // The escape hatch short-circuits all detection: anything that isn't
+    // already valid UTF-8 (including BOM-tagged UTF-16) is skipped, preserving
+    // the old "skip on first non-UTF-8 byte" behavior.
+    if ascii_only {
+        return std::str::from_utf8(bytes).ok().map(|s| s.to_owned());
+    }
+
+    if let Some(enc) = sniff_bom(bytes) {
+        let (text, _, _) = enc.decode(bytes);
+        return Some(text.into_owned());
+    }
+
+    match std::str::from_utf8(bytes) {
+        // Fast path: borrow-free validation, no transcode cost.
+        Ok(s) => Some(s.to_owned()),
+        Err(_) => {
+            let mut detector = chardetng::EncodingDetector::new();
+            detector.feed(bytes, true);
+            let enc = detector.guess(None, true);
+            let (text, _, _) = enc.decode(bytes);
+            Some(text.into_owned())
+        }
+    }
+}
+
+/// Map a leading byte-order mark to its `encoding_rs` encoding, if present.
+fn sniff_bom(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    match bytes {
+        [0xFF, 0xFE, ..] => Some(encoding_rs::UTF_16LE),
+        [0xFE, 0xFF, ..] => Some(encoding_rs::UTF_16BE),
+        [0xEF, 0xBB, 0xBF, ..] => Some(encoding_rs::UTF_8),
+        _ => None,
+    }
+}
+
+fn scan_file(
+    path: &PathBuf,
+    pattern: &Regex,
+    max_size: u64,
+    ascii_only: bool,
+    signatures: &[Signature],
+    min_run: usize,
+) -> Vec<Finding> {
     let meta = match std::fs::metadata(path) {
         Ok(m) => m,
         Err(_) => return vec![],
@@ -56,37 +245,216 @@ fn scan_file(path: &PathBuf, pattern: &Regex, max_size: u64) -> Vec<Finding> {
         Err(_) => return vec![],
     };
 
-    let reader = BufReader::new(file);
+    // Map the whole file once. For valid UTF-8 (the common case) we scan the
+    // mapped bytes in place; only files that need transcoding pay for an owned
+    // buffer via `decode_bytes`.
+    let mmap = match unsafe { Mmap::map(&file) } {
+        Ok(m) => m,
+        Err(_) => return vec![],
+    };
+
+    let decoded;
+    let contents: &str = match std::str::from_utf8(&mmap) {
+        Ok(s) => s,
+        Err(_) => {
+            decoded = match decode_bytes(&mmap, ascii_only) {
+                Some(c) => c,
+                None => return vec![],
+            };
+            &decoded
+        }
+    };
+
     let mut findings = Vec::new();
 
-    for (idx, line_result) in reader.lines().enumerate() {
-        let line = match line_result {
-            Ok(l) => l,
-            Err(_) => break, // binary file or encoding issue
-        };
-
-        if let Some(mat) = pattern.find(&line) {
-            let ws_count = mat.end() - mat.start();
-            let preview = if line.len() > 120 {
-                let mut end = 120;
-                while !line.is_char_boundary(end) {
-                    end -= 1;
+    if signatures.contains(&Signature::Whitespace) {
+        scan_whitespace_runs(path, contents, pattern, min_run, &mut findings);
+    }
+
+    let unicode_sigs: Vec<Signature> = signatures
+        .iter()
+        .copied()
+        .filter(|s| *s != Signature::Whitespace)
+        .collect();
+    if !unicode_sigs.is_empty() {
+        scan_unicode_signatures(path, contents, &unicode_sigs, min_run, &mut findings);
+    }
+
+    findings
+}
+
+/// The ASCII whitespace-run signature: a single pass over the whole buffer
+/// with `regex::bytes`. `line_num` is recovered by counting newlines from the
+/// last match position, keeping the total work O(n) across all matches.
+///
+/// The match may span newlines (to catch padding split across lines), but we
+/// require at least `min_run` actual space/tab bytes so a run of only blank
+/// lines isn't mistaken for space/tab padding. `ws_count` reports the
+/// non-newline padding length, not the raw match byte length.
+fn scan_whitespace_runs(
+    path: &PathBuf,
+    contents: &str,
+    pattern: &Regex,
+    min_run: usize,
+    findings: &mut Vec<Finding>,
+) {
+    let haystack = contents.as_bytes();
+    let mut line_num = 1usize;
+    let mut cursor = 0usize;
+    for mat in pattern.find_iter(haystack) {
+        line_num += bytecount_newlines(&haystack[cursor..mat.start()]);
+        cursor = mat.start();
+
+        let ws_count = haystack[mat.start()..mat.end()]
+            .iter()
+            .filter(|&&b| b == b' ' || b == b'\t')
+            .count();
+        if ws_count < min_run {
+            continue;
+        }
+
+        findings.push(Finding {
+            path: path.clone(),
+            line_num,
+            category: Signature::Whitespace,
+            ws_count,
+            code_points: Vec::new(),
+            line_preview: line_preview(haystack, mat.start(), mat.end()),
+        });
+    }
+}
+
+/// The Unicode signatures need to examine individual code points, so they scan
+/// `char`s line by line rather than bytes. Each enabled signature contributes
+/// at most one finding per line, carrying the offending code points.
+fn scan_unicode_signatures(
+    path: &PathBuf,
+    contents: &str,
+    signatures: &[Signature],
+    min_run: usize,
+    findings: &mut Vec<Finding>,
+) {
+    for (idx, line) in contents.lines().enumerate() {
+        for &sig in signatures {
+            let mut points = Vec::new();
+            match sig {
+                Signature::BidiControl => {
+                    points.extend(line.chars().filter(|&c| is_bidi_control(c)));
                 }
-                format!("{}...", &line[..end])
-            } else {
-                line.clone()
-            };
+                Signature::ZeroWidth => {
+                    points.extend(zero_width_mid_token(line));
+                }
+                Signature::UnicodeWhitespace => {
+                    // Only a *long run* of Unicode whitespace is suspicious; a
+                    // lone U+00A0 is routine in real source/markup. Mirror the
+                    // ASCII whitespace signature's threshold semantics.
+                    points.extend(long_unicode_whitespace_runs(line, min_run));
+                }
+                Signature::Whitespace => unreachable!("handled by scan_whitespace_runs"),
+            }
+
+            if points.is_empty() {
+                continue;
+            }
 
             findings.push(Finding {
                 path: path.clone(),
                 line_num: idx + 1,
-                ws_count,
-                line_preview: preview,
+                category: sig,
+                ws_count: points.len(),
+                code_points: points.iter().map(|&c| code_point(c)).collect(),
+                line_preview: preview_str(line),
             });
         }
     }
+}
 
-    findings
+/// Collect zero-width characters that sit *between* two non-whitespace
+/// characters — i.e. embedded mid-token, where they are most likely malicious
+/// rather than incidental (e.g. a leading BOM).
+fn zero_width_mid_token(line: &str) -> Vec<char> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut hits = Vec::new();
+    for i in 0..chars.len() {
+        if !is_zero_width(chars[i]) {
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1).copied();
+        if matches!(prev, Some(c) if !c.is_whitespace())
+            && matches!(next, Some(c) if !c.is_whitespace())
+        {
+            hits.push(chars[i]);
+        }
+    }
+    hits
+}
+
+/// Collect the code points belonging to contiguous runs of Unicode whitespace
+/// that are at least `min_run` long. Shorter runs are ignored, so a single
+/// stray non-breaking space does not trigger a finding.
+fn long_unicode_whitespace_runs(line: &str, min_run: usize) -> Vec<char> {
+    let mut hits = Vec::new();
+    let mut run: Vec<char> = Vec::new();
+    for c in line.chars() {
+        if is_unicode_whitespace(c) {
+            run.push(c);
+        } else {
+            if run.len() >= min_run {
+                hits.extend(run.drain(..));
+            } else {
+                run.clear();
+            }
+        }
+    }
+    if run.len() >= min_run {
+        hits.extend(run);
+    }
+    hits
+}
+
+/// Length-capped preview of a single already-decoded line.
+fn preview_str(line: &str) -> String {
+    if line.len() > 120 {
+        let mut cut = 120;
+        while !line.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        format!("{}...", &line[..cut])
+    } else {
+        line.to_string()
+    }
+}
+
+/// Count `\n` bytes in a slice.
+fn bytecount_newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&b| b == b'\n').count()
+}
+
+/// Build a lossy, length-capped preview of the line(s) surrounding a match by
+/// scanning back to the preceding newline and forward to the next one.
+fn line_preview(haystack: &[u8], start: usize, end: usize) -> String {
+    let line_start = haystack[..start]
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let line_end = haystack[end..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map(|i| end + i)
+        .unwrap_or(haystack.len());
+
+    let line = String::from_utf8_lossy(&haystack[line_start..line_end]);
+    if line.len() > 120 {
+        let mut cut = 120;
+        while !line.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        format!("{}...", &line[..cut])
+    } else {
+        line.into_owned()
+    }
 }
 
 fn main() {
@@ -94,7 +462,10 @@ fn main() {
     let start = Instant::now();
 
     let ws_min = cli.min_whitespace;
-    let pattern_str = format!(r"[ \t]{{{},}}", ws_min);
+    // Include `\r`/`\n` in the class so a padding run that straddles line
+    // boundaries is caught as one match — the per-line baseline (and a
+    // `[ \t]`-only class) breaks the run at every newline and misses it.
+    let pattern_str = format!(r"[ \t\r\n]{{{},}}", ws_min);
     let pattern = Regex::new(&pattern_str).expect("invalid regex");
 
     eprintln!(
@@ -102,80 +473,239 @@ fn main() {
         cli.path, ws_min
     );
 
-    let files_scanned = AtomicUsize::new(0);
+    let files_scanned = Arc::new(AtomicUsize::new(0));
 
-    // Collect file paths — scan everything, skip known junk directories
-    let paths: Vec<PathBuf> = WalkBuilder::new(&cli.path)
-        .hidden(false)
-        .git_ignore(false)
-        .git_global(false)
-        .git_exclude(false)
-        .filter_entry(|entry| {
-            let name = entry.file_name().to_string_lossy();
-            !matches!(
-                name.as_ref(),
-                "node_modules" | ".git" | "vendor" | ".pnpm" | "dist" | "build" | ".cache"
-                    | "__pycache__" | ".venv" | "venv" | ".tox"
-            )
-        })
-        .build()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_some_and(|ft| ft.is_file()))
-        .map(|e| e.into_path())
-        .collect();
+    // Build the include/exclude matchers. When the user passes their own
+    // excludes we honour those verbatim; otherwise fall back to skipping the
+    // well-known junk directories.
+    let includes = Arc::new(build_globset(&cli.include));
+    let exclude_patterns: Vec<String> = if cli.exclude.is_empty() {
+        DEFAULT_EXCLUDES.iter().map(|d| format!("**/{d}")).collect()
+    } else {
+        cli.exclude.clone()
+    };
+    let excludes = build_globset(&exclude_patterns);
 
-    eprintln!("Found {} files to scan", paths.len());
+    // Findings flow over a channel from the walker's worker threads to a single
+    // printer thread. Streaming is the default so results appear as they are
+    // discovered and peak memory stays bounded; `--sort` (and the machine
+    // formats, which need the whole array) buffer instead.
+    // Bounded so a lagging printer applies backpressure to the parallel
+    // workers and the in-flight queue cannot grow without limit.
+    let (tx, rx) = mpsc::sync_channel::<Finding>(1024);
+    let buffering = !matches!(cli.output, OutputFormat::Text) || cli.sort;
+    let output = cli.output;
+    let verbose = cli.verbose;
+    let printer = thread::spawn(move || print_loop(rx, buffering, output, ws_min, verbose));
 
-    // Scan in parallel
-    let all_findings: Vec<Finding> = paths
-        .par_iter()
-        .flat_map(|path| {
-            files_scanned.fetch_add(1, Ordering::Relaxed);
-            scan_file(path, &pattern, cli.max_size)
-        })
-        .collect();
+    let pattern = Arc::new(pattern);
+    let signatures = Arc::new(cli.signatures.clone());
+    let max_size = cli.max_size;
+    let ascii_only = cli.ascii_only;
+    let min_run = cli.min_whitespace;
+
+    // Prune excluded directories early (before descending) via `filter_entry`.
+    WalkBuilder::new(&cli.path)
+        .hidden(false)
+        .git_ignore(cli.respect_gitignore)
+        .git_global(cli.respect_gitignore)
+        .git_exclude(cli.respect_gitignore)
+        .filter_entry(move |entry| !excludes.is_match(entry.path()))
+        .build_parallel()
+        .run(|| {
+            let tx = tx.clone();
+            let pattern = Arc::clone(&pattern);
+            let signatures = Arc::clone(&signatures);
+            let includes = Arc::clone(&includes);
+            let files_scanned = Arc::clone(&files_scanned);
+            Box::new(move |result| {
+                let entry = match result {
+                    Ok(e) => e,
+                    Err(_) => return WalkState::Continue,
+                };
+                if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+                    return WalkState::Continue;
+                }
+                let path = entry.into_path();
+                if !(includes.is_empty() || includes.is_match(&path)) {
+                    return WalkState::Continue;
+                }
+                files_scanned.fetch_add(1, Ordering::Relaxed);
+                for finding in
+                    scan_file(&path, &pattern, max_size, ascii_only, &signatures, min_run)
+                {
+                    if tx.send(finding).is_err() {
+                        return WalkState::Quit;
+                    }
+                }
+                WalkState::Continue
+            })
+        });
+
+    // Drop the last sender so the printer's channel iterator terminates.
+    drop(tx);
+    let found_any = printer.join().expect("printer thread panicked");
 
     let elapsed = start.elapsed();
     let total_scanned = files_scanned.load(Ordering::Relaxed);
 
-    if all_findings.is_empty() {
+    eprintln!();
+    eprintln!(
+        "Scanned {} files in {:.2}s",
+        total_scanned,
+        elapsed.as_secs_f64()
+    );
+
+    // Non-zero exit when anything was flagged, so CI can fail builds deterministically.
+    if found_any {
+        std::process::exit(1);
+    }
+}
+
+/// The single printer thread. In streaming mode it prints each finding as it
+/// arrives (grouping consecutive findings from the same file); in buffering
+/// mode it collects everything and defers to the chosen report formatter.
+/// Returns whether any finding was seen, for the process exit code.
+fn print_loop(
+    rx: mpsc::Receiver<Finding>,
+    buffering: bool,
+    output: OutputFormat,
+    ws_min: usize,
+    verbose: bool,
+) -> bool {
+    if buffering {
+        let mut findings: Vec<Finding> = rx.iter().collect();
+        // Group deterministically by file, then by line, for `--sort`.
+        findings.sort_by(|a, b| (&a.path, a.line_num).cmp(&(&b.path, b.line_num)));
+        match output {
+            OutputFormat::Text => report_text(&findings, ws_min, verbose),
+            OutputFormat::Json => report_json(&findings),
+            OutputFormat::Sarif => report_sarif(&findings),
+        }
+        return !findings.is_empty();
+    }
+
+    let mut any = false;
+    let mut current_file: Option<PathBuf> = None;
+    for finding in rx {
+        if !any {
+            println!();
+            println!("FINDINGS (streaming):");
+            println!();
+            any = true;
+        }
+        if current_file.as_ref() != Some(&finding.path) {
+            println!("  {}", finding.path.display());
+            current_file = Some(finding.path.clone());
+        }
+        print_finding(&finding, verbose);
+    }
+
+    if !any {
+        eprintln!();
+        eprintln!("No findings.");
+    }
+    any
+}
+
+/// Print the per-finding detail lines shared by the text and streaming output.
+fn print_finding(finding: &Finding, verbose: bool) {
+    println!(
+        "    Line {}: {} [{}]",
+        finding.line_num,
+        finding.ws_count,
+        finding.category.slug()
+    );
+    if verbose {
+        if !finding.code_points.is_empty() {
+            println!("      code points: {}", finding.code_points.join(", "));
+        }
+        println!("      {}", finding.line_preview);
+    }
+}
+
+fn report_text(findings: &[Finding], ws_min: usize, verbose: bool) {
+    if findings.is_empty() {
         eprintln!();
         eprintln!(
             "No files with {}+ consecutive whitespace chars found.",
             ws_min
         );
-    } else {
-        let unique_files: std::collections::HashSet<&PathBuf> =
-            all_findings.iter().map(|f| &f.path).collect();
+        return;
+    }
 
-        println!();
-        println!(
-            "FOUND {} file(s) with {}+ consecutive whitespace chars:",
-            unique_files.len(),
-            ws_min
-        );
-        println!();
+    let unique_files: std::collections::HashSet<&PathBuf> =
+        findings.iter().map(|f| &f.path).collect();
 
-        let mut current_file: Option<&PathBuf> = None;
-        for finding in &all_findings {
-            if current_file != Some(&finding.path) {
-                println!("  {}", finding.path.display());
-                current_file = Some(&finding.path);
-            }
-            println!(
-                "    Line {}: {} whitespace chars",
-                finding.line_num, finding.ws_count
-            );
-            if cli.verbose {
-                println!("      {}", finding.line_preview);
-            }
+    println!();
+    println!("FOUND {} file(s) with obfuscation signatures:", unique_files.len());
+    println!();
+
+    let mut current_file: Option<&PathBuf> = None;
+    for finding in findings {
+        if current_file != Some(&finding.path) {
+            println!("  {}", finding.path.display());
+            current_file = Some(&finding.path);
         }
+        print_finding(finding, verbose);
     }
+}
 
-    eprintln!();
-    eprintln!(
-        "Scanned {} files in {:.2}s",
-        total_scanned,
-        elapsed.as_secs_f64()
+fn report_json(findings: &[Finding]) {
+    let json = serde_json::to_string_pretty(findings).expect("serialize findings");
+    println!("{}", json);
+}
+
+/// Emit a minimal SARIF 2.1.0 log with one `result` per finding so the report
+/// can be uploaded to code-scanning tools. The `ws_count` drives the severity:
+/// larger runs are more likely to be deliberate obfuscation, so they escalate
+/// from `warning` to `error`.
+fn report_sarif(findings: &[Finding]) {
+    let results: Vec<_> = findings
+        .iter()
+        .map(|f| {
+            let level = if f.ws_count >= 200 { "error" } else { "warning" };
+            serde_json::json!({
+                "ruleId": f.category.slug(),
+                "level": level,
+                "message": {
+                    "text": format!(
+                        "{} flagged code point(s) [{}]",
+                        f.ws_count,
+                        f.category.slug()
+                    )
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": f.path.display().to_string() },
+                        "region": { "startLine": f.line_num }
+                    }
+                }]
+            })
+        })
+        .collect();
+
+    let rules: Vec<_> = Signature::all()
+        .iter()
+        .map(|s| serde_json::json!({ "id": s.slug() }))
+        .collect();
+
+    let sarif = serde_json::json!({
+        "version": "2.1.0",
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "sandworm",
+                    "rules": rules
+                }
+            },
+            "results": results
+        }]
+    });
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&sarif).expect("serialize sarif")
     );
 }